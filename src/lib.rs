@@ -0,0 +1,10 @@
+//! A fast, minimal-allocation parser and writer for Standard MIDI Files.
+
+mod prelude;
+
+pub(crate) mod primitive;
+pub mod riff;
+pub mod stream;
+
+pub use crate::primitive::{Format, Fps, SmpteTime, Timing};
+pub use crate::stream::SmfStream;
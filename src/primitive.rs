@@ -118,6 +118,28 @@ macro_rules! restricted_int {
         $( int_feature!{$name ; $inner : $feature} )*
     };
 }
+/// Derives serde impls on the restricted integers: serialize as the inner int, deserialize back
+/// through `try_from` so the bit-width invariant is enforced and out-of-range values are rejected.
+///
+/// Scoped to the types the `serde` feature advertises (`u4`/`u7`/`u14`/`u15`/`u24`/`u28`); the
+/// internal-only `u2` is intentionally left out.
+#[cfg(feature = "serde")]
+macro_rules! serde_restricted_int {
+    {$( $name:ident : $inner:tt ),* $(,)?} => {$(
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, ser: S) -> StdResult<S::Ok, S::Error> {
+                self.0.serialize(ser)
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(de: D) -> StdResult<Self, D::Error> {
+                let raw = <$inner>::deserialize(de)?;
+                Self::try_from(raw)
+                    .ok_or_else(|| serde::de::Error::custom(concat!("value out of range for ", stringify!($name))))
+            }
+        }
+    )*};
+}
 restricted_int! {u15: u16 => 15; read}
 restricted_int! {u14: u16 => 14; read read_u7}
 restricted_int! {u7: u8 => 7; read}
@@ -142,6 +164,15 @@ restricted_int! {
     /// Referred to in the MIDI spec as "variable length int".
     u28: u32 => 28;
 }
+#[cfg(feature = "serde")]
+serde_restricted_int! {
+    u4: u8,
+    u7: u8,
+    u14: u16,
+    u15: u16,
+    u24: u32,
+    u28: u32,
+}
 impl IntReadBottom7 for u28 {
     fn read_u7(raw: &mut &[u8]) -> StdResult<Self, &'static ErrorKind> {
         let mut int: u32 = 0;
@@ -174,9 +205,53 @@ impl IntReadBottom7 for u28 {
     }
 }
 
+/// A byte sink the encoders write into, abstracting over `io::Write` (under `std`) and an
+/// in-memory `Vec<u8>` (under `alloc`).
+///
+/// Splitting the write paths over this trait lets `alloc`-only embedded targets serialize SMF
+/// data into a `Vec<u8>` without pulling in `std`.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub(crate) trait Sink {
+    /// The error produced by a failed write: `io::Error` for `io::Write` sinks, uninhabited for
+    /// the infallible `Vec<u8>` sink.
+    type Error;
+    fn write_all(&mut self, buf: &[u8]) -> StdResult<(), Self::Error>;
+}
 #[cfg(feature = "std")]
+impl<W: Write> Sink for W {
+    type Error = IoError;
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        Write::write_all(self, buf)
+    }
+}
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+impl Sink for &mut Vec<u8> {
+    type Error = core::convert::Infallible;
+    fn write_all(&mut self, buf: &[u8]) -> StdResult<(), core::convert::Infallible> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+/// Failure while encoding into a [`Sink`], layering the "chunk too long" data error over whatever
+/// error the underlying sink produces.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub(crate) enum EncodeError<E> {
+    /// The underlying sink failed.
+    Sink(E),
+    /// A slice length did not fit in the 28-bit varlen encoding.
+    ChunkTooLong,
+}
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl<E> From<E> for EncodeError<E> {
+    fn from(err: E) -> Self {
+        Self::Sink(err)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
 impl u28 {
-    pub(crate) fn write_varlen<W: Write>(&self, out: &mut W) -> IoResult<()> {
+    pub(crate) fn write_varlen<S: Sink>(&self, out: &mut S) -> StdResult<(), S::Error> {
         let int = self.as_int();
         let mut skipping = true;
         for i in (0..4).rev() {
@@ -217,13 +292,16 @@ pub(crate) fn read_varlen_slice<'a>(raw: &mut &'a [u8]) -> Result<&'a [u8]> {
     })
 }
 
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "alloc"))]
 /// Write a slice represented as a varlen `u28` as its length and then the raw bytes.
-pub(crate) fn write_varlen_slice<W: Write>(slice: &[u8], out: &mut W) -> IoResult<()> {
+pub(crate) fn write_varlen_slice<S: Sink>(
+    slice: &[u8],
+    out: &mut S,
+) -> StdResult<(), EncodeError<S::Error>> {
     let len = u32::try_from(slice.len())
         .ok()
         .and_then(|len| u28::try_from(len))
-        .ok_or_else(|| IoError::new(io::ErrorKind::InvalidInput, "chunk size exceeds 28 bits"))?;
+        .ok_or(EncodeError::ChunkTooLong)?;
     len.write_varlen(out)?;
     out.write_all(slice)?;
     Ok(())
@@ -231,6 +309,7 @@ pub(crate) fn write_varlen_slice<W: Write>(slice: &[u8], out: &mut W) -> IoResul
 
 /// The order in which tracks should be laid out when playing back this SMF file.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Format {
     /// This file should have a single track only.
     ///
@@ -257,7 +336,7 @@ impl Format {
             _ => bail!(err_invalid!("invalid smf format")),
         })
     }
-    #[cfg(feature = "std")]
+    #[cfg(any(feature = "std", feature = "alloc"))]
     pub fn encode(&self) -> [u8; 2] {
         let code: u16 = match self {
             Self::SingleTrack => 0,
@@ -271,6 +350,7 @@ impl Format {
 /// The timing for an SMF file.
 /// This can be in ticks/beat or ticks/second.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Timing {
     /// Specifies ticks/beat as a 15-bit integer.
     ///
@@ -298,7 +378,7 @@ impl Timing {
             Ok(Self::Metrical(u15::from(raw)))
         }
     }
-    #[cfg(feature = "std")]
+    #[cfg(any(feature = "std", feature = "alloc"))]
     pub fn encode(&self) -> [u8; 2] {
         match self {
             Self::Metrical(ticksperbeat) => ticksperbeat.as_int().to_be_bytes(),
@@ -346,8 +426,21 @@ impl SmpteTime {
         check!(hour < 24);
         check!(minute < 60);
         check!(second < 60);
-        check!(frame < fps.as_int());
+        //`Fps29` is 29.97 drop-frame, whose timeline numbers 30 frames (0..=29) despite
+        //`as_int()` reporting 29; the others number exactly `fps` frames.
+        let frame_count = match fps {
+            Fps::Fps29 => 30,
+            _ => fps.as_int(),
+        };
+        check!(frame < frame_count);
         check!(subframe < 100);
+        //Under the `strict` feature, reject the two frame numbers that drop-frame timecode skips
+        //at the start of every minute that is not a multiple of ten.
+        check!(!(cfg!(feature = "strict")
+            && matches!(fps, Fps::Fps29)
+            && minute % 10 != 0
+            && second == 0
+            && frame < 2));
         Some(Self {
             hour,
             minute,
@@ -379,6 +472,74 @@ impl SmpteTime {
         self.second as f32
             + ((self.frame as f32 + self.subframe as f32 / 100.0) / self.fps.as_f32())
     }
+    /// The absolute frame number this timecode names, counting from `00:00:00:00`.
+    ///
+    /// For 24/25/30 fps this is simply `((hour*60 + minute)*60 + second)*fps + frame`. For
+    /// `Fps::Fps29` (29.97) the timeline uses SMPTE drop-frame numbering, which skips frame
+    /// numbers 0 and 1 at the start of every minute except those divisible by ten, so those
+    /// skipped frames are subtracted out.
+    pub fn to_frame_number(&self) -> u32 {
+        let total_minutes = self.hour as u32 * 60 + self.minute as u32;
+        let total_seconds = total_minutes * 60 + self.second as u32;
+        match self.fps {
+            Fps::Fps29 => {
+                let dropped = 2 * (total_minutes - total_minutes / 10);
+                (total_seconds * 30 + self.frame as u32) - dropped
+            }
+            fps => total_seconds * fps.as_int() as u32 + self.frame as u32,
+        }
+    }
+    /// The inverse of [`to_frame_number`](SmpteTime::to_frame_number): reconstructs the timecode
+    /// naming `frame_number` at the given `fps`, re-inserting the frames that drop-frame numbering
+    /// skips. The subframe is always zero, since a frame number carries no subframe information.
+    ///
+    /// Returns `None` if the resulting fields fall outside the [`SmpteTime::new`] ranges.
+    pub fn from_frame_number(frame_number: u32, fps: Fps) -> Option<Self> {
+        let (hour, minute, second, frame) = match fps {
+            Fps::Fps29 => {
+                //Classic drop-frame expansion: walk back the dropped frames per ten-minute block
+                //and per trailing minute to recover the nominal 30 fps frame count.
+                const FRAMES_PER_10MIN: u32 = 17982;
+                const FRAMES_PER_MIN: u32 = 1800;
+                const DROP: u32 = 2;
+                let blocks = frame_number / FRAMES_PER_10MIN;
+                let rem = frame_number % FRAMES_PER_10MIN;
+                let nominal = frame_number.checked_add(
+                    DROP * 9 * blocks
+                        + if rem > 1 {
+                            DROP * ((rem - DROP) / (FRAMES_PER_MIN - DROP))
+                        } else {
+                            0
+                        },
+                )?;
+                (
+                    nominal / 30 / 60 / 60,
+                    nominal / 30 / 60 % 60,
+                    nominal / 30 % 60,
+                    nominal % 30,
+                )
+            }
+            fps => {
+                let fps_int = fps.as_int() as u32;
+                let total_seconds = frame_number / fps_int;
+                (
+                    total_seconds / 60 / 60,
+                    total_seconds / 60 % 60,
+                    total_seconds % 60,
+                    frame_number % fps_int,
+                )
+            }
+        };
+        Self::new(u8::try_from(hour).ok()?, minute as u8, second as u8, frame as u8, 0, fps)
+    }
+    /// Wall-clock seconds from `00:00:00:00`, correct for drop-frame `Fps::Fps29` content.
+    ///
+    /// Computed as the drop-frame-aware [`to_frame_number`](SmpteTime::to_frame_number) divided by
+    /// the real fps (`30 / 1.001` for `Fps29`), unlike [`second_f32`](SmpteTime::second_f32),
+    /// which is only the naive offset within the current minute.
+    pub fn as_seconds_f64(&self) -> f64 {
+        self.to_frame_number() as f64 / self.fps.as_f64()
+    }
     pub fn read(raw: &mut &[u8]) -> Result<Self> {
         let data = raw
             .split_checked(5)
@@ -393,7 +554,7 @@ impl SmpteTime {
         Ok(Self::new(hour, minute, second, frame, subframe, fps)
             .ok_or(err_invalid!("invalid smpte time"))?)
     }
-    #[cfg(feature = "std")]
+    #[cfg(any(feature = "std", feature = "alloc"))]
     pub fn encode(&self) -> [u8; 5] {
         let hour_fps = self.hour() | self.fps().as_code().as_int() << 5;
         [
@@ -406,8 +567,52 @@ impl SmpteTime {
     }
 }
 
+/// Field-by-field shadow of [`SmpteTime`], used to route serde through [`SmpteTime::new`] so the
+/// hour/minute/second/frame/subframe range guarantees are enforced rather than bypassed.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "SmpteTime")]
+struct SmpteTimeRepr {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    frame: u8,
+    subframe: u8,
+    fps: Fps,
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for SmpteTime {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> StdResult<S::Ok, S::Error> {
+        SmpteTimeRepr {
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+            frame: self.frame,
+            subframe: self.subframe,
+            fps: self.fps,
+        }
+        .serialize(ser)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SmpteTime {
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> StdResult<Self, D::Error> {
+        let repr = SmpteTimeRepr::deserialize(de)?;
+        Self::new(
+            repr.hour,
+            repr.minute,
+            repr.second,
+            repr.frame,
+            repr.subframe,
+            repr.fps,
+        )
+        .ok_or_else(|| serde::de::Error::custom("smpte time out of range"))
+    }
+}
+
 /// One of the four FPS values available for SMPTE times, as defined by the MIDI standard.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Fps {
     /// 24 frames per second.
     Fps24,
@@ -469,4 +674,91 @@ impl Fps {
             _ => unreachable!(),
         }
     }
+    /// Get the actual `f64` fps out, retaining the precision the `29.97 = 30 / 1.001` ratio loses
+    /// when evaluated in `f32`.
+    pub fn as_f64(self) -> f64 {
+        match self.as_int() {
+            24 => 24.0,
+            25 => 25.0,
+            29 => 30.0 / 1.001,
+            30 => 30.0,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_number_roundtrips_non_drop() {
+        for fps in [Fps::Fps24, Fps::Fps25, Fps::Fps30] {
+            for &(h, m, s, f) in &[(0, 0, 0, 0), (1, 2, 3, 4), (23, 59, 59, 20)] {
+                if f >= fps.as_int() {
+                    continue;
+                }
+                let t = SmpteTime::new(h, m, s, f, 0, fps).unwrap();
+                assert_eq!(SmpteTime::from_frame_number(t.to_frame_number(), fps), Some(t));
+            }
+        }
+    }
+
+    #[test]
+    fn frame_number_roundtrips_drop_frame() {
+        //The awkward cases that drop-frame numbering skips around.
+        for &(h, m, s, f) in &[(0, 1, 0, 2), (0, 9, 0, 2), (0, 10, 0, 0), (1, 0, 0, 0)] {
+            let t = SmpteTime::new(h, m, s, f, 0, Fps::Fps29).unwrap();
+            assert_eq!(
+                SmpteTime::from_frame_number(t.to_frame_number(), Fps::Fps29),
+                Some(t)
+            );
+        }
+    }
+
+    #[test]
+    fn fps29_admits_frame_29() {
+        //29.97 drop-frame numbers 30 frames despite `as_int()` being 29.
+        assert!(SmpteTime::new(0, 0, 0, 29, 0, Fps::Fps29).is_some());
+        assert!(SmpteTime::new(0, 0, 0, 30, 0, Fps::Fps29).is_none());
+        //30 fps still caps at 30 frame numbers.
+        assert!(SmpteTime::new(0, 0, 0, 30, 0, Fps::Fps30).is_none());
+    }
+
+    #[test]
+    fn seconds_spot_values() {
+        let t = SmpteTime::new(0, 0, 1, 0, 0, Fps::Fps30).unwrap();
+        assert!((t.as_seconds_f64() - 1.0).abs() < 1e-9);
+        let t = SmpteTime::new(0, 0, 0, 12, 0, Fps::Fps24).unwrap();
+        assert!((t.as_seconds_f64() - 0.5).abs() < 1e-9);
+        //One hour of drop-frame timecode lands just shy of 3600 real seconds.
+        let t = SmpteTime::new(1, 0, 0, 0, 0, Fps::Fps29).unwrap();
+        assert!((t.as_seconds_f64() - 107892.0 / (30.0 / 1.001)).abs() < 1e-6);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn restricted_int_rejects_out_of_range() {
+        assert!(serde_json::from_str::<u14>("16383").is_ok());
+        assert!(serde_json::from_str::<u14>("16384").is_err());
+        assert!(serde_json::from_str::<u7>("127").is_ok());
+        assert!(serde_json::from_str::<u7>("128").is_err());
+    }
+
+    #[test]
+    fn smpte_deserialize_routed_through_new() {
+        let good = serde_json::json!({
+            "hour": 1, "minute": 2, "second": 3, "frame": 4, "subframe": 5, "fps": "Fps30"
+        });
+        assert!(serde_json::from_value::<SmpteTime>(good).is_ok());
+        //An out-of-range hour must fail rather than bypass the `new` invariant.
+        let bad = serde_json::json!({
+            "hour": 99, "minute": 2, "second": 3, "frame": 4, "subframe": 5, "fps": "Fps30"
+        });
+        assert!(serde_json::from_value::<SmpteTime>(bad).is_err());
+    }
 }
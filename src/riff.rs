@@ -45,3 +45,150 @@ pub fn unwrap(raw: &[u8]) -> Option<&[u8]> {
     }
     None
 }
+
+/// The inner SMF together with the sibling chunks that can ride alongside it in an `RIFF/RMID`
+/// container, borrowed out of the input.
+///
+/// Produced by [`parse`], the inverse of which is [`wrap`].
+#[cfg(feature = "std")]
+pub struct Rmid<'a> {
+    /// The inner SMF, i.e. the `data` chunk.
+    pub data: &'a [u8],
+    /// The entries of the `INFO` LIST as `(id, value)` pairs, e.g. `(*b"INAM", ...)`.
+    pub info: Vec<([u8; 4], &'a [u8])>,
+    /// The chunks of an embedded `DLS ` instrument bank, if the file carries one.
+    ///
+    /// This is the body of the nested `RIFF`/`DLS ` file, i.e. everything past its `DLS ` form
+    /// type, exactly what [`wrap`] expects back in [`RmidInfo::dls`].
+    pub dls: Option<&'a [u8]>,
+}
+
+/// The owned counterpart of [`Rmid`], describing the auxiliary chunks to emit when building a
+/// container with [`wrap`].
+#[cfg(feature = "std")]
+#[derive(Clone, Default, Debug)]
+pub struct RmidInfo {
+    /// The entries of the `INFO` LIST as `(id, value)` pairs, e.g. `(*b"INAM", ...)`.
+    pub info: Vec<([u8; 4], Vec<u8>)>,
+    /// An embedded `DLS ` instrument bank to place alongside the MIDI, given as the body of the
+    /// nested `RIFF`/`DLS ` file (everything past the `DLS ` form type).
+    pub dls: Option<Vec<u8>>,
+}
+
+/// Like [`unwrap`], but also collects the sibling chunks that many real-world RMID files carry:
+/// the `INFO` LIST metadata and an embedded `DLS ` soundbank.
+#[cfg(feature = "std")]
+pub fn parse(raw: &[u8]) -> Option<Rmid<'_>> {
+    let (id, mut riff) = ChunkIter(raw).next()?;
+    if &id != b"RIFF" {
+        return None;
+    }
+    let formtype = riff.split_checked(4)?;
+    if formtype != b"RMID" {
+        return None;
+    }
+    let mut data = None;
+    let mut info = Vec::new();
+    let mut dls = None;
+    for (id, chunk) in ChunkIter(riff) {
+        match &id {
+            b"data" => data = Some(chunk),
+            b"RIFF" => {
+                //A spec-compliant DLS bank is embedded as a complete nested RIFF file whose form
+                //type is `DLS `, not as a bare `DLS ` chunk.
+                let mut nested = chunk;
+                if nested.split_checked(4) == Some(&b"DLS "[..]) {
+                    dls = Some(nested);
+                }
+            }
+            b"LIST" => {
+                let mut list = chunk;
+                if list.split_checked(4) == Some(&b"INFO"[..]) {
+                    for (sub_id, value) in ChunkIter(list) {
+                        info.push((sub_id, value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(Rmid {
+        data: data?,
+        info,
+        dls,
+    })
+}
+
+/// Builds a valid `RIFF/RMID` file carrying `smf` under its `data` chunk, plus whatever
+/// auxiliary chunks are described by `info`.
+#[cfg(feature = "std")]
+pub fn wrap(smf: &[u8], info: &RmidInfo) -> Vec<u8> {
+    /// Appends an `id`/`data` chunk, taking care of the little-endian length and the trailing
+    /// padding byte for odd-length data.
+    fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(id);
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            out.push(0);
+        }
+    }
+    let mut body = Vec::new();
+    body.extend_from_slice(b"RMID");
+    if !info.info.is_empty() {
+        let mut list = Vec::new();
+        list.extend_from_slice(b"INFO");
+        for (id, value) in &info.info {
+            push_chunk(&mut list, id, value);
+        }
+        push_chunk(&mut body, b"LIST", &list);
+    }
+    push_chunk(&mut body, b"data", smf);
+    if let Some(dls) = &info.dls {
+        //Emit the soundbank as a complete nested `RIFF`/`DLS ` file so real players recognize it.
+        let mut nested = Vec::with_capacity(4 + dls.len());
+        nested.extend_from_slice(b"DLS ");
+        nested.extend_from_slice(dls);
+        push_chunk(&mut body, b"RIFF", &nested);
+    }
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&body);
+    out
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_parse_roundtrip() {
+        let smf = &b"MThd"[..];
+        let info = RmidInfo {
+            //`ICOP` is deliberately odd-length to exercise the pad byte on write and its skip on
+            //read; the parsed value must come back without the padding.
+            info: vec![(*b"INAM", b"Song".to_vec()), (*b"ICOP", b"odd".to_vec())],
+            dls: Some(b"dlsbankbody".to_vec()),
+        };
+        let bytes = wrap(smf, &info);
+        let parsed = parse(&bytes).expect("wrapped container should parse");
+        assert_eq!(parsed.data, smf);
+        assert_eq!(parsed.info.len(), 2);
+        assert_eq!(parsed.info[0], (*b"INAM", &b"Song"[..]));
+        assert_eq!(parsed.info[1], (*b"ICOP", &b"odd"[..]));
+        assert_eq!(parsed.dls, Some(&b"dlsbankbody"[..]));
+    }
+
+    #[test]
+    fn wrap_without_aux_chunks() {
+        let smf = &b"MThd\x00\x00\x00\x06"[..];
+        let bytes = wrap(smf, &RmidInfo::default());
+        let parsed = parse(&bytes).expect("wrapped container should parse");
+        assert_eq!(parsed.data, smf);
+        assert!(parsed.info.is_empty());
+        assert_eq!(parsed.dls, None);
+        //`unwrap` should reach the same inner SMF.
+        assert_eq!(unwrap(&bytes), Some(smf));
+    }
+}
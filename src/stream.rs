@@ -0,0 +1,183 @@
+//! Incremental, streaming parsing of Standard MIDI Files over `io::Read`.
+//!
+//! Unlike the slice-based API, which requires the entire file to be resident in memory before
+//! any parsing can begin, [`SmfStream`] consumes just the `MThd` header up front to yield the
+//! file [`Format`] and [`Timing`], and then pulls one `MTrk` chunk at a time into a reusable
+//! buffer. This lets multi-megabyte SMF files be processed off a `BufReader` with bounded
+//! memory. The input must start at the `MThd` header: the `RIFF` wrapper of an RMID file is not
+//! stripped.
+
+use crate::prelude::*;
+use crate::primitive::{Format, IntRead, SplitChecked, Timing};
+
+/// A streaming reader over a Standard MIDI File.
+///
+/// Created with [`SmfStream::new`], which reads and validates the `MThd` header and exposes the
+/// file [`Format`] and [`Timing`]. Tracks are then read lazily with
+/// [`next_track`](SmfStream::next_track), which reads exactly the declared `MTrk` length into an
+/// internal buffer reused across chunks and hands back the raw track body, ready for the
+/// slice-based per-event primitives.
+#[cfg(feature = "std")]
+pub struct SmfStream<R> {
+    reader: R,
+    format: Format,
+    timing: Timing,
+    track_count: u16,
+    tracks_read: u16,
+    buf: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> SmfStream<R> {
+    /// Reads and validates the `MThd` header off `reader`, leaving it positioned at the first
+    /// chunk that follows.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut header = [0; 14];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| err_invalid!("failed to read the smf header"))?;
+        let mut raw = &header[..];
+        let id = raw
+            .split_checked(4)
+            .ok_or(err_invalid!("failed to read header magic"))?;
+        if id != b"MThd" {
+            bail!(err_invalid!("not a standard midi file"));
+        }
+        let header_len = u32::read(&mut raw)?;
+        let format = Format::read(&mut raw)?;
+        let track_count = u16::read(&mut raw)?;
+        let timing = Timing::read(&mut raw)?;
+        //The spec fixes the header body at 6 bytes, but tolerant readers skip any extra declared
+        //bytes rather than mistaking them for the first chunk.
+        if header_len > 6 {
+            io::copy(
+                &mut reader.by_ref().take((header_len - 6) as u64),
+                &mut io::sink(),
+            )
+            .map_err(|_| err_invalid!("failed to skip extended header"))?;
+        }
+        Ok(Self {
+            reader,
+            format,
+            timing,
+            track_count,
+            tracks_read: 0,
+            buf: Vec::new(),
+        })
+    }
+
+    /// The track layout declared by the header.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// The tick timing declared by the header.
+    pub fn timing(&self) -> Timing {
+        self.timing
+    }
+
+    /// The number of `MTrk` chunks declared by the header.
+    pub fn track_count(&self) -> u16 {
+        self.track_count
+    }
+
+    /// Reads the next `MTrk` chunk body into the internal buffer and returns it as a slice.
+    ///
+    /// Non-`MTrk` chunks are skipped in accordance with the SMF spec. Returns `Ok(None)` once
+    /// every declared track has been consumed.
+    pub fn next_track(&mut self) -> Result<Option<&[u8]>> {
+        if self.tracks_read >= self.track_count {
+            return Ok(None);
+        }
+        loop {
+            let mut chunk_header = [0; 8];
+            self.reader
+                .read_exact(&mut chunk_header)
+                .map_err(|_| err_invalid!("failed to read track chunk header"))?;
+            let len = u32::from_be_bytes([
+                chunk_header[4],
+                chunk_header[5],
+                chunk_header[6],
+                chunk_header[7],
+            ]);
+            //Read the declared chunk length incrementally so that a forged header cannot drive a
+            //multi-gigabyte allocation before a single body byte has been read: `take` bounds the
+            //read to `len` and `read_to_end` only grows the buffer as actual bytes arrive. Reading
+            //the whole chunk keeps any varlen or event straddling the boundary contained within
+            //the buffer for the slice helpers, which already model truncated lengths under the
+            //`strict` feature.
+            self.buf.clear();
+            let read = self
+                .reader
+                .by_ref()
+                .take(len as u64)
+                .read_to_end(&mut self.buf)
+                .map_err(|_| err_invalid!("incomplete track chunk"))?;
+            if read as u64 != len as u64 {
+                bail!(err_invalid!("incomplete track chunk"));
+            }
+            if &chunk_header[..4] == b"MTrk" {
+                self.tracks_read += 1;
+                return Ok(Some(&self.buf[..]));
+            }
+            //Alien chunk: drop it and keep looking for the next track.
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal format-1 SMF with a leading alien chunk and two `MTrk` tracks.
+    fn sample_smf() -> Vec<u8> {
+        let mut v = Vec::new();
+        v.extend_from_slice(b"MThd");
+        v.extend_from_slice(&6u32.to_be_bytes());
+        v.extend_from_slice(&1u16.to_be_bytes()); //Format::Parallel
+        v.extend_from_slice(&2u16.to_be_bytes()); //two tracks
+        v.extend_from_slice(&96u16.to_be_bytes()); //metrical, 96 ticks/beat
+        //An alien chunk that must be skipped on the way to the first track.
+        v.extend_from_slice(b"XYZ ");
+        v.extend_from_slice(&3u32.to_be_bytes());
+        v.extend_from_slice(&[1, 2, 3]);
+        let track1 = [0x00, 0xFF, 0x2F, 0x00];
+        v.extend_from_slice(b"MTrk");
+        v.extend_from_slice(&(track1.len() as u32).to_be_bytes());
+        v.extend_from_slice(&track1);
+        let track2 = [0x00, 0x90, 0x40, 0x7F, 0x00, 0xFF, 0x2F, 0x00];
+        v.extend_from_slice(b"MTrk");
+        v.extend_from_slice(&(track2.len() as u32).to_be_bytes());
+        v.extend_from_slice(&track2);
+        v
+    }
+
+    #[test]
+    fn header_and_lazy_tracks() {
+        let mut smf = SmfStream::new(io::Cursor::new(sample_smf())).unwrap();
+        assert_eq!(smf.format(), Format::Parallel);
+        assert_eq!(smf.track_count(), 2);
+        assert!(matches!(smf.timing(), Timing::Metrical(_)));
+        let first = smf.next_track().unwrap().unwrap().to_vec();
+        assert_eq!(first, vec![0x00, 0xFF, 0x2F, 0x00]);
+        let second = smf.next_track().unwrap().unwrap().to_vec();
+        assert_eq!(second.len(), 8);
+        assert!(smf.next_track().unwrap().is_none());
+    }
+
+    #[test]
+    fn truncated_track_errors() {
+        let mut v = Vec::new();
+        v.extend_from_slice(b"MThd");
+        v.extend_from_slice(&6u32.to_be_bytes());
+        v.extend_from_slice(&0u16.to_be_bytes()); //Format::SingleTrack
+        v.extend_from_slice(&1u16.to_be_bytes());
+        v.extend_from_slice(&96u16.to_be_bytes());
+        //Declares ten body bytes but only three follow.
+        v.extend_from_slice(b"MTrk");
+        v.extend_from_slice(&10u32.to_be_bytes());
+        v.extend_from_slice(&[0x00, 0xFF, 0x2F]);
+        let mut smf = SmfStream::new(io::Cursor::new(v)).unwrap();
+        assert!(smf.next_track().is_err());
+    }
+}